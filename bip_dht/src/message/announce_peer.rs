@@ -0,0 +1,161 @@
+use std::collections::{BTreeMap};
+
+use bip_bencode::{Bencode, BencodeConvert, Dictionary};
+use bip_util::bt::{NodeId, InfoHash};
+
+use message::{self};
+use message::request::{self, RequestValidate};
+use message::response::{self, ResponseValidate};
+use error::{DhtResult};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AnnouncePeerRequest<'a> {
+    trans_id:     &'a [u8],
+    version:      Option<&'a [u8]>,
+    node_id:      NodeId,
+    info_hash:    InfoHash,
+    port:         u16,
+    implied_port: bool,
+    token:        &'a [u8]
+}
+
+impl<'a> AnnouncePeerRequest<'a> {
+    pub fn new(trans_id: &'a [u8], node_id: NodeId, info_hash: InfoHash, token: &'a [u8], port: u16,
+        implied_port: bool) -> AnnouncePeerRequest<'a> {
+        AnnouncePeerRequest{ trans_id: trans_id, version: None, node_id: node_id, info_hash: info_hash,
+            port: port, implied_port: implied_port, token: token }
+    }
+
+    pub fn with_version(trans_id: &'a [u8], version: &'a [u8], node_id: NodeId, info_hash: InfoHash,
+        token: &'a [u8], port: u16, implied_port: bool) -> AnnouncePeerRequest<'a> {
+        AnnouncePeerRequest{ trans_id: trans_id, version: Some(version), node_id: node_id, info_hash: info_hash,
+            port: port, implied_port: implied_port, token: token }
+    }
+
+    pub fn from_parts(rqst_root: &Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8], version: Option<&'a [u8]>)
+        -> DhtResult<AnnouncePeerRequest<'a>> {
+        let validate = RequestValidate::new(trans_id);
+        let node_id_bytes = try!(validate.lookup_and_convert_bytes(rqst_root, message::NODE_ID_KEY));
+        let info_hash_bytes = try!(validate.lookup_and_convert_bytes(rqst_root, message::INFO_HASH_KEY));
+        let token = try!(validate.lookup_and_convert_bytes(rqst_root, message::TOKEN_KEY));
+        let port = try!(validate.lookup_and_convert_int(rqst_root, message::PORT_KEY));
+        let implied_port = validate.lookup_and_convert_int(rqst_root, message::IMPLIED_PORT_KEY)
+            .map(|value| value != 0).unwrap_or(false);
+
+        let node_id = try!(validate.validate_node_id(node_id_bytes));
+        let info_hash = try!(validate.validate_info_hash(info_hash_bytes));
+
+        Ok(match version {
+            Some(version) => AnnouncePeerRequest::with_version(trans_id, version, node_id, info_hash, token,
+                port as u16, implied_port),
+            None          => AnnouncePeerRequest::new(trans_id, node_id, info_hash, token, port as u16,
+                implied_port)
+        })
+    }
+
+    pub fn transaction_id(&self) -> &'a [u8] {
+        self.trans_id
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn info_hash(&self) -> InfoHash {
+        self.info_hash
+    }
+
+    pub fn token(&self) -> &'a [u8] {
+        self.token
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn implied_port(&self) -> bool {
+        self.implied_port
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message_root = BTreeMap::new();
+
+        message_root.insert(message::TRANSACTION_ID_KEY, ben_bytes!(self.trans_id));
+        message_root.insert(message::MESSAGE_TYPE_KEY, ben_bytes!(message::REQUEST_TYPE_KEY));
+        message_root.insert(message::REQUEST_TYPE_KEY, ben_bytes!(request::ANNOUNCE_PEER_TYPE_KEY));
+        if let Some(version) = self.version {
+            message_root.insert(message::CLIENT_TYPE_KEY, ben_bytes!(version));
+        }
+        message_root.insert(request::REQUEST_ARGS_KEY, ben_map!{
+            message::NODE_ID_KEY => ben_bytes!(self.node_id.as_bytes()),
+            message::INFO_HASH_KEY => ben_bytes!(self.info_hash.as_bytes()),
+            message::PORT_KEY => ben_int!(self.port as i64),
+            message::IMPLIED_PORT_KEY => ben_int!(self.implied_port as i64),
+            message::TOKEN_KEY => ben_bytes!(self.token)
+        });
+
+        Bencode::Dict(message_root).encode()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AnnouncePeerResponse<'a> {
+    trans_id: &'a [u8],
+    version:  Option<&'a [u8]>,
+    node_id:  NodeId
+}
+
+impl<'a> AnnouncePeerResponse<'a> {
+    pub fn new(trans_id: &'a [u8], node_id: NodeId) -> AnnouncePeerResponse<'a> {
+        AnnouncePeerResponse{ trans_id: trans_id, version: None, node_id: node_id }
+    }
+
+    pub fn with_version(trans_id: &'a [u8], version: &'a [u8], node_id: NodeId) -> AnnouncePeerResponse<'a> {
+        AnnouncePeerResponse{ trans_id: trans_id, version: Some(version), node_id: node_id }
+    }
+
+    pub fn from_parts(rsp_root: &Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8], version: Option<&'a [u8]>)
+        -> DhtResult<AnnouncePeerResponse<'a>> {
+        let validate = ResponseValidate::new(trans_id);
+        let node_id_bytes = try!(validate.lookup_and_convert_bytes(rsp_root, message::NODE_ID_KEY));
+
+        let node_id = try!(validate.validate_node_id(node_id_bytes));
+
+        Ok(match version {
+            Some(version) => AnnouncePeerResponse::with_version(trans_id, version, node_id),
+            None          => AnnouncePeerResponse::new(trans_id, node_id)
+        })
+    }
+
+    pub fn transaction_id(&self) -> &'a [u8] {
+        self.trans_id
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message_root = BTreeMap::new();
+
+        message_root.insert(message::TRANSACTION_ID_KEY, ben_bytes!(self.trans_id));
+        message_root.insert(message::MESSAGE_TYPE_KEY, ben_bytes!(message::RESPONSE_TYPE_KEY));
+        message_root.insert(message::REQUEST_TYPE_KEY, ben_bytes!(request::ANNOUNCE_PEER_TYPE_KEY));
+        if let Some(version) = self.version {
+            message_root.insert(message::CLIENT_TYPE_KEY, ben_bytes!(version));
+        }
+        message_root.insert(response::RESPONSE_ARGS_KEY, ben_map!{
+            message::NODE_ID_KEY => ben_bytes!(self.node_id.as_bytes())
+        });
+
+        Bencode::Dict(message_root).encode()
+    }
+}
@@ -0,0 +1,111 @@
+//! Compact representations of nodes and peer contact information.
+
+use std::net::{SocketAddr, SocketAddrV4, SocketAddrV6, Ipv4Addr, Ipv6Addr};
+
+use bip_bencode::{Bencode};
+use bip_util::bt::{NODE_ID_LEN};
+
+use error::{DhtResult, DhtErrorKind, DhtError};
+
+/// Length, in bytes, of a single compact peer entry carrying an IPv4 address (BEP-5).
+pub const IPV4_VALUE_LEN: usize = 6;
+/// Length, in bytes, of a single compact peer entry carrying an IPv6 address (BEP-32).
+pub const IPV6_VALUE_LEN: usize = 18;
+/// Length, in bytes, of a single compact node info entry (a `NodeId` plus an IPv4 socket address).
+pub const NODE_INFO_LEN: usize = NODE_ID_LEN + IPV4_VALUE_LEN;
+
+/// Compact (20 byte id + 6 byte ipv4 socket address) node contact information.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CompactNodeInfo<'a> {
+    nodes: &'a [u8]
+}
+
+impl<'a> CompactNodeInfo<'a> {
+    pub fn new(nodes: &'a [u8]) -> CompactNodeInfo<'a> {
+        CompactNodeInfo{ nodes: nodes }
+    }
+
+    pub fn nodes(&self) -> &'a [u8] {
+        self.nodes
+    }
+}
+
+/// Compact peer contact information as returned under the `values` key of a `get_peers` response.
+///
+/// Per BEP-5, `values` is a bencoded list whose elements are each a single compact peer; every
+/// element is validated up front so that `sockets` never has to reject a malformed entry.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CompactValueInfo<'a> {
+    values: &'a [Bencode<'a>]
+}
+
+impl<'a> CompactValueInfo<'a> {
+    /// Validate that every element of `values` is a well formed compact peer entry.
+    pub fn new(values: &'a [Bencode<'a>]) -> DhtResult<CompactValueInfo<'a>> {
+        for value in values {
+            let bytes = try!(value.bytes().ok_or(DhtError::new(DhtErrorKind::InvalidResponse,
+                "Values Entry Was Not A Byte String")));
+
+            match bytes.len() {
+                IPV4_VALUE_LEN | IPV6_VALUE_LEN => (),
+                _ => return Err(DhtError::new(DhtErrorKind::InvalidResponse,
+                    "Values Entry Was Not A Valid 6 Or 18 Byte Compact Peer"))
+            }
+        }
+
+        Ok(CompactValueInfo{ values: values })
+    }
+
+    pub fn values(&self) -> &'a [Bencode<'a>] {
+        self.values
+    }
+
+    /// Iterate over each compact peer entry as a parsed `SocketAddr`.
+    pub fn sockets(&self) -> CompactValueSockets<'a> {
+        CompactValueSockets{ index: 0, values: self.values }
+    }
+}
+
+/// Iterator over the `SocketAddr`s held within a `CompactValueInfo`.
+pub struct CompactValueSockets<'a> {
+    index:  usize,
+    values: &'a [Bencode<'a>]
+}
+
+impl<'a> Iterator for CompactValueSockets<'a> {
+    type Item = SocketAddr;
+
+    fn next(&mut self) -> Option<SocketAddr> {
+        if let Some(value) = self.values.get(self.index) {
+            self.index += 1;
+
+            // Validated up front in CompactValueInfo::new, so the bytes and length are known good.
+            let bytes = value.bytes().expect("bip_dht: CompactValueInfo Held A Non Byte String Value");
+            Some(bytes_to_socket_addr(bytes))
+        } else {
+            None
+        }
+    }
+}
+
+fn bytes_to_socket_addr(bytes: &[u8]) -> SocketAddr {
+    match bytes.len() {
+        IPV4_VALUE_LEN => {
+            let ip = Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]);
+            let port = ((bytes[4] as u16) << 8) | (bytes[5] as u16);
+
+            SocketAddr::V4(SocketAddrV4::new(ip, port))
+        },
+        IPV6_VALUE_LEN => {
+            let mut octets = [0u8; 16];
+            for (dst, src) in octets.iter_mut().zip(bytes[0..16].iter()) {
+                *dst = *src;
+            }
+            let ip = Ipv6Addr::from(octets);
+            let port = ((bytes[16] as u16) << 8) | (bytes[17] as u16);
+
+            SocketAddr::V6(SocketAddrV6::new(ip, port, 0, 0))
+        },
+        _ => unreachable!("bip_dht: CompactValueInfo Held An Invalid Length Value")
+    }
+}
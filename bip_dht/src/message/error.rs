@@ -0,0 +1,88 @@
+use std::collections::{BTreeMap};
+
+use bip_bencode::{Bencode, BencodeConvert, Dictionary};
+
+use message::{self};
+use message::request::{RequestValidate};
+use error::{DhtResult, DhtErrorKind, DhtError};
+
+/// A generic error occurred.
+pub const GENERIC_ERROR: i64 = 201;
+/// The node/client is misbehaving in some way that isn't a protocol violation.
+pub const SERVER_ERROR: i64 = 202;
+/// The request does not conform to the KRPC protocol, eg a missing argument.
+pub const PROTOCOL_ERROR: i64 = 203;
+/// The requested method is not known to the receiving node.
+pub const METHOD_UNKNOWN_ERROR: i64 = 204;
+
+/// A KRPC error message (`"y" = "e"`) returned by a remote node in place of a response.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ErrorMessage<'a> {
+    trans_id: &'a [u8],
+    version:  Option<&'a [u8]>,
+    code:     i64,
+    message:  &'a [u8]
+}
+
+impl<'a> ErrorMessage<'a> {
+    pub fn new(trans_id: &'a [u8], code: i64, message: &'a [u8]) -> ErrorMessage<'a> {
+        ErrorMessage{ trans_id: trans_id, version: None, code: code, message: message }
+    }
+
+    pub fn with_version(trans_id: &'a [u8], version: &'a [u8], code: i64, message: &'a [u8]) -> ErrorMessage<'a> {
+        ErrorMessage{ trans_id: trans_id, version: Some(version), code: code, message: message }
+    }
+
+    pub fn from_parts(msg_root: &Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8], version: Option<&'a [u8]>)
+        -> DhtResult<ErrorMessage<'a>> {
+        let validate = RequestValidate::new(trans_id);
+        let error_list = try!(validate.lookup_and_convert_list(msg_root, message::ERROR_TYPE_KEY));
+
+        if error_list.len() != 2 {
+            return Err(DhtError::new(DhtErrorKind::InvalidMessage,
+                "Error List Did Not Contain Exactly Two Elements"));
+        }
+
+        let code = try!(error_list[0].int().ok_or(
+            DhtError::new(DhtErrorKind::InvalidMessage, "Error Code Was Not An Integer")));
+        let desc = try!(error_list[1].bytes().ok_or(
+            DhtError::new(DhtErrorKind::InvalidMessage, "Error Message Was Not A Byte String")));
+
+        Ok(match version {
+            Some(version) => ErrorMessage::with_version(trans_id, version, code, desc),
+            None          => ErrorMessage::new(trans_id, code, desc)
+        })
+    }
+
+    pub fn transaction_id(&self) -> &'a [u8] {
+        self.trans_id
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn code(&self) -> i64 {
+        self.code
+    }
+
+    pub fn message(&self) -> &'a [u8] {
+        self.message
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message_root = BTreeMap::new();
+
+        message_root.insert(message::TRANSACTION_ID_KEY, ben_bytes!(self.trans_id));
+        message_root.insert(message::MESSAGE_TYPE_KEY, ben_bytes!(message::ERROR_TYPE_KEY));
+        if let Some(version) = self.version {
+            message_root.insert(message::CLIENT_TYPE_KEY, ben_bytes!(version));
+        }
+        message_root.insert(message::ERROR_TYPE_KEY, Bencode::List(vec![
+            ben_int!(self.code),
+            ben_bytes!(self.message)
+        ]));
+
+        Bencode::Dict(message_root).encode()
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::{BTreeMap};
+
+use bip_bencode::{Bencode, BencodeConvert, Dictionary};
+use bip_util::bt::{NodeId};
+
+use message::{self};
+use message::compact_info::{CompactNodeInfo};
+use message::request::{self, RequestValidate};
+use message::response::{self, ResponseValidate};
+use error::{DhtResult};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FindNodeRequest<'a> {
+    trans_id: &'a [u8],
+    version:  Option<&'a [u8]>,
+    node_id:  NodeId,
+    target:   NodeId
+}
+
+impl<'a> FindNodeRequest<'a> {
+    pub fn new(trans_id: &'a [u8], node_id: NodeId, target: NodeId) -> FindNodeRequest<'a> {
+        FindNodeRequest{ trans_id: trans_id, version: None, node_id: node_id, target: target }
+    }
+
+    pub fn with_version(trans_id: &'a [u8], version: &'a [u8], node_id: NodeId, target: NodeId)
+        -> FindNodeRequest<'a> {
+        FindNodeRequest{ trans_id: trans_id, version: Some(version), node_id: node_id, target: target }
+    }
+
+    pub fn from_parts(rqst_root: &Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8], version: Option<&'a [u8]>)
+        -> DhtResult<FindNodeRequest<'a>> {
+        let validate = RequestValidate::new(trans_id);
+        let node_id_bytes = try!(validate.lookup_and_convert_bytes(rqst_root, message::NODE_ID_KEY));
+        let target_bytes = try!(validate.lookup_and_convert_bytes(rqst_root, message::TARGET_ID_KEY));
+
+        let node_id = try!(validate.validate_node_id(node_id_bytes));
+        let target = try!(validate.validate_node_id(target_bytes));
+
+        Ok(match version {
+            Some(version) => FindNodeRequest::with_version(trans_id, version, node_id, target),
+            None          => FindNodeRequest::new(trans_id, node_id, target)
+        })
+    }
+
+    pub fn transaction_id(&self) -> &'a [u8] {
+        self.trans_id
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn target_id(&self) -> NodeId {
+        self.target
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message_root = BTreeMap::new();
+
+        message_root.insert(message::TRANSACTION_ID_KEY, ben_bytes!(self.trans_id));
+        message_root.insert(message::MESSAGE_TYPE_KEY, ben_bytes!(message::REQUEST_TYPE_KEY));
+        message_root.insert(message::REQUEST_TYPE_KEY, ben_bytes!(request::FIND_NODE_TYPE_KEY));
+        if let Some(version) = self.version {
+            message_root.insert(message::CLIENT_TYPE_KEY, ben_bytes!(version));
+        }
+        message_root.insert(request::REQUEST_ARGS_KEY, ben_map!{
+            message::NODE_ID_KEY => ben_bytes!(self.node_id.as_bytes()),
+            message::TARGET_ID_KEY => ben_bytes!(self.target.as_bytes())
+        });
+
+        Bencode::Dict(message_root).encode()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FindNodeResponse<'a> {
+    trans_id: &'a [u8],
+    version:  Option<&'a [u8]>,
+    node_id:  NodeId,
+    nodes:    CompactNodeInfo<'a>
+}
+
+impl<'a> FindNodeResponse<'a> {
+    pub fn new(trans_id: &'a [u8], node_id: NodeId, nodes: CompactNodeInfo<'a>) -> FindNodeResponse<'a> {
+        FindNodeResponse{ trans_id: trans_id, version: None, node_id: node_id, nodes: nodes }
+    }
+
+    pub fn with_version(trans_id: &'a [u8], version: &'a [u8], node_id: NodeId, nodes: CompactNodeInfo<'a>)
+        -> FindNodeResponse<'a> {
+        FindNodeResponse{ trans_id: trans_id, version: Some(version), node_id: node_id, nodes: nodes }
+    }
+
+    pub fn from_parts(rsp_root: &'a Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8], version: Option<&'a [u8]>)
+        -> DhtResult<FindNodeResponse<'a>> {
+        let validate = ResponseValidate::new(trans_id);
+        let node_id_bytes = try!(validate.lookup_and_convert_bytes(rsp_root, message::NODE_ID_KEY));
+        let nodes_bytes = try!(validate.lookup_and_convert_bytes(rsp_root, message::NODES_KEY));
+
+        let node_id = try!(validate.validate_node_id(node_id_bytes));
+        let nodes = try!(validate.validate_nodes(nodes_bytes));
+
+        Ok(match version {
+            Some(version) => FindNodeResponse::with_version(trans_id, version, node_id, nodes),
+            None          => FindNodeResponse::new(trans_id, node_id, nodes)
+        })
+    }
+
+    pub fn transaction_id(&self) -> &'a [u8] {
+        self.trans_id
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn nodes(&self) -> CompactNodeInfo<'a> {
+        self.nodes
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message_root = BTreeMap::new();
+
+        message_root.insert(message::TRANSACTION_ID_KEY, ben_bytes!(self.trans_id));
+        message_root.insert(message::MESSAGE_TYPE_KEY, ben_bytes!(message::RESPONSE_TYPE_KEY));
+        message_root.insert(message::REQUEST_TYPE_KEY, ben_bytes!(request::FIND_NODE_TYPE_KEY));
+        if let Some(version) = self.version {
+            message_root.insert(message::CLIENT_TYPE_KEY, ben_bytes!(version));
+        }
+        message_root.insert(response::RESPONSE_ARGS_KEY, ben_map!{
+            message::NODE_ID_KEY => ben_bytes!(self.node_id.as_bytes()),
+            message::NODES_KEY => ben_bytes!(self.nodes.nodes())
+        });
+
+        Bencode::Dict(message_root).encode()
+    }
+}
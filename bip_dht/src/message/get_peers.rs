@@ -2,6 +2,7 @@ use std::borrow::{Cow, IntoCow};
 use std::collections::{BTreeMap};
 
 use bip_bencode::{Bencode, BencodeConvert, Dictionary};
+use bip_util::bt::{NodeId, InfoHash};
 
 use message::{self};
 use message::compact_info::{CompactNodeInfo, CompactValueInfo};
@@ -12,50 +13,67 @@ use error::{DhtResult, DhtErrorKind, DhtError};
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct GetPeersRequest<'a> {
     trans_id:  &'a [u8],
-    node_id:   &'a [u8],
-    info_hash: &'a [u8]
+    version:   Option<&'a [u8]>,
+    node_id:   NodeId,
+    info_hash: InfoHash
 }
 
 impl<'a> GetPeersRequest<'a> {
-    pub fn new(trans_id: &'a [u8], node_id: &'a [u8], info_hash: &'a [u8]) -> DhtResult<GetPeersRequest<'a>> {
-        let validate = RequestValidate::new(&trans_id);
-        try!(validate.validate_node_id(&node_id));
-        try!(validate.validate_node_id(&info_hash));
-        
-        Ok(GetPeersRequest{ trans_id: trans_id, node_id: node_id, info_hash: info_hash })
+    pub fn new(trans_id: &'a [u8], node_id: NodeId, info_hash: InfoHash) -> GetPeersRequest<'a> {
+        GetPeersRequest{ trans_id: trans_id, version: None, node_id: node_id, info_hash: info_hash }
+    }
+
+    pub fn with_version(trans_id: &'a [u8], version: &'a [u8], node_id: NodeId, info_hash: InfoHash)
+        -> GetPeersRequest<'a> {
+        GetPeersRequest{ trans_id: trans_id, version: Some(version), node_id: node_id, info_hash: info_hash }
     }
 
-    pub fn from_parts(rqst_root: &Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8]) -> DhtResult<GetPeersRequest<'a>> {
+    pub fn from_parts(rqst_root: &Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8], version: Option<&'a [u8]>)
+        -> DhtResult<GetPeersRequest<'a>> {
         let validate = RequestValidate::new(trans_id);
-        let node_id = try!(validate.lookup_and_convert_bytes(rqst_root, message::NODE_ID_KEY));
-        let info_hash = try!(validate.lookup_and_convert_bytes(rqst_root, message::INFO_HASH_KEY));
-        
-        GetPeersRequest::new(trans_id, node_id, info_hash)
+        let node_id_bytes = try!(validate.lookup_and_convert_bytes(rqst_root, message::NODE_ID_KEY));
+        let info_hash_bytes = try!(validate.lookup_and_convert_bytes(rqst_root, message::INFO_HASH_KEY));
+
+        let node_id = try!(validate.validate_node_id(node_id_bytes));
+        let info_hash = try!(validate.validate_info_hash(info_hash_bytes));
+
+        Ok(match version {
+            Some(version) => GetPeersRequest::with_version(trans_id, version, node_id, info_hash),
+            None          => GetPeersRequest::new(trans_id, node_id, info_hash)
+        })
     }
-    
+
     pub fn transaction_id(&self) -> &'a [u8] {
         &self.trans_id
     }
-    
-    pub fn node_id(&self) -> &'a [u8] {
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn node_id(&self) -> NodeId {
         self.node_id
     }
-    
-    pub fn info_hash(&self) -> &'a [u8] {
+
+    pub fn info_hash(&self) -> InfoHash {
         self.info_hash
     }
-    
+
     pub fn encode(&self) -> Vec<u8> {
-        (ben_map!{
-            //message::CLIENT_TYPE_KEY => ben_bytes!(dht::CLIENT_IDENTIFICATION),
-            message::TRANSACTION_ID_KEY => ben_bytes!(self.trans_id),
-            message::MESSAGE_TYPE_KEY => ben_bytes!(message::REQUEST_TYPE_KEY),
-            message::REQUEST_TYPE_KEY => ben_bytes!(request::GET_PEERS_TYPE_KEY),
-            request::REQUEST_ARGS_KEY => ben_map!{
-                message::NODE_ID_KEY => ben_bytes!(self.node_id),
-                message::INFO_HASH_KEY => ben_bytes!(self.info_hash)
-            }
-        }).encode()
+        let mut message_root = BTreeMap::new();
+
+        message_root.insert(message::TRANSACTION_ID_KEY, ben_bytes!(self.trans_id));
+        message_root.insert(message::MESSAGE_TYPE_KEY, ben_bytes!(message::REQUEST_TYPE_KEY));
+        message_root.insert(message::REQUEST_TYPE_KEY, ben_bytes!(request::GET_PEERS_TYPE_KEY));
+        if let Some(version) = self.version {
+            message_root.insert(message::CLIENT_TYPE_KEY, ben_bytes!(version));
+        }
+        message_root.insert(request::REQUEST_ARGS_KEY, ben_map!{
+            message::NODE_ID_KEY => ben_bytes!(self.node_id.as_bytes()),
+            message::INFO_HASH_KEY => ben_bytes!(self.info_hash.as_bytes())
+        });
+
+        Bencode::Dict(message_root).encode()
     }
 }
 
@@ -69,7 +87,8 @@ pub enum CompactInfoType<'a> {
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct GetPeersResponse<'a> {
     trans_id:  &'a [u8],
-    node_id:   &'a [u8],
+    version:   Option<&'a [u8]>,
+    node_id:   NodeId,
     // It looks like bootstrap nodes don't provide a nodes key, probably
     // because they are only used for bootstraping and not to announce to.
     token:     Option<&'a [u8]>,
@@ -77,20 +96,24 @@ pub struct GetPeersResponse<'a> {
 }
 
 impl<'a> GetPeersResponse<'a> {
-    pub fn new(trans_id: &'a [u8], node_id: &'a [u8], token: Option<&'a [u8]>, info_type: CompactInfoType<'a>)
-        -> DhtResult<GetPeersResponse<'a>> {
-        let validate = ResponseValidate::new(&trans_id);
-        try!(validate.validate_node_id(&node_id));
-        
-        Ok(GetPeersResponse{ trans_id: trans_id, node_id: node_id, token: token, info_type: info_type })
+    pub fn new(trans_id: &'a [u8], node_id: NodeId, token: Option<&'a [u8]>, info_type: CompactInfoType<'a>)
+        -> GetPeersResponse<'a> {
+        GetPeersResponse{ trans_id: trans_id, version: None, node_id: node_id, token: token, info_type: info_type }
     }
 
-    pub fn from_parts(rsp_root: &'a Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8])
+    pub fn with_version(trans_id: &'a [u8], version: &'a [u8], node_id: NodeId, token: Option<&'a [u8]>,
+        info_type: CompactInfoType<'a>) -> GetPeersResponse<'a> {
+        GetPeersResponse{ trans_id: trans_id, version: Some(version), node_id: node_id, token: token,
+            info_type: info_type }
+    }
+
+    pub fn from_parts(rsp_root: &'a Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8], version: Option<&'a [u8]>)
         -> DhtResult<GetPeersResponse<'a>> {
         let validate = ResponseValidate::new(trans_id);
-        let node_id = try!(validate.lookup_and_convert_bytes(rsp_root, message::NODE_ID_KEY));
+        let node_id_bytes = try!(validate.lookup_and_convert_bytes(rsp_root, message::NODE_ID_KEY));
+        let node_id = try!(validate.validate_node_id(node_id_bytes));
         let token = validate.lookup_and_convert_bytes(rsp_root, message::TOKEN_KEY).ok();
-        
+
         let maybe_nodes = validate.lookup_and_convert_bytes(rsp_root, message::NODES_KEY);
         let maybe_values = validate.lookup_and_convert_list(rsp_root, message::VALUES_KEY);
         
@@ -116,21 +139,28 @@ impl<'a> GetPeersResponse<'a> {
             }
         };
         
-        GetPeersResponse::new(trans_id, node_id, token, info_type)
+        Ok(match version {
+            Some(version) => GetPeersResponse::with_version(trans_id, version, node_id, token, info_type),
+            None          => GetPeersResponse::new(trans_id, node_id, token, info_type)
+        })
     }
-    
+
     pub fn transaction_id(&self) -> &'a [u8] {
         self.trans_id
     }
-    
-    pub fn node_id(&self) -> &'a [u8] {
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn node_id(&self) -> NodeId {
         self.node_id
     }
-    
+
     pub fn token(&self) -> Option<&'a [u8]> {
         self.token
     }
-    
+
     pub fn info_type(&self) -> CompactInfoType<'a> {
         self.info_type
     }
@@ -138,7 +168,7 @@ impl<'a> GetPeersResponse<'a> {
     pub fn encode(&self) -> Vec<u8> {
         let mut response_args = BTreeMap::new();
         
-        response_args.insert(message::NODE_ID_KEY, ben_bytes!(self.node_id));
+        response_args.insert(message::NODE_ID_KEY, ben_bytes!(self.node_id.as_bytes()));
         match self.token {
             Some(token) => {
                 response_args.insert(message::TOKEN_KEY, ben_bytes!(token));
@@ -159,12 +189,16 @@ impl<'a> GetPeersResponse<'a> {
             }
         };
         
-        (ben_map!{
-            //message::CLIENT_TYPE_KEY => ben_bytes!(dht::CLIENT_IDENTIFICATION),
-            message::TRANSACTION_ID_KEY => ben_bytes!(self.trans_id),
-            message::MESSAGE_TYPE_KEY => ben_bytes!(message::RESPONSE_TYPE_KEY),
-            message::REQUEST_TYPE_KEY => ben_bytes!(request::GET_PEERS_TYPE_KEY),
-            response::RESPONSE_ARGS_KEY => Bencode::Dict(response_args)
-        }).encode()
+        let mut message_root = BTreeMap::new();
+
+        message_root.insert(message::TRANSACTION_ID_KEY, ben_bytes!(self.trans_id));
+        message_root.insert(message::MESSAGE_TYPE_KEY, ben_bytes!(message::RESPONSE_TYPE_KEY));
+        message_root.insert(message::REQUEST_TYPE_KEY, ben_bytes!(request::GET_PEERS_TYPE_KEY));
+        if let Some(version) = self.version {
+            message_root.insert(message::CLIENT_TYPE_KEY, ben_bytes!(version));
+        }
+        message_root.insert(response::RESPONSE_ARGS_KEY, Bencode::Dict(response_args));
+
+        Bencode::Dict(message_root).encode()
     }
 }
\ No newline at end of file
@@ -0,0 +1,128 @@
+//! Messages sent and received as part of the KRPC protocol used by the dht.
+
+use bip_bencode::{Bencode, BencodeConvert, Dictionary};
+
+use message::request::{self, RequestValidate};
+use message::response::{self};
+use message::announce_peer::{AnnouncePeerRequest};
+use message::find_node::{FindNodeRequest};
+use message::get_peers::{GetPeersRequest};
+use message::ping::{PingRequest};
+use error::{DhtResult, DhtErrorKind, DhtError};
+
+pub mod announce_peer;
+pub mod compact_info;
+pub mod error;
+pub mod find_node;
+pub mod get_peers;
+pub mod ping;
+pub mod request;
+pub mod response;
+
+pub const CLIENT_TYPE_KEY:    &'static str = "v";
+pub const TRANSACTION_ID_KEY: &'static str = "t";
+pub const MESSAGE_TYPE_KEY:   &'static str = "y";
+pub const REQUEST_TYPE_KEY:   &'static str = "q";
+pub const RESPONSE_TYPE_KEY:  &'static str = "r";
+pub const ERROR_TYPE_KEY:     &'static str = "e";
+
+pub const NODE_ID_KEY:      &'static str = "id";
+pub const TARGET_ID_KEY:    &'static str = "target";
+pub const INFO_HASH_KEY:    &'static str = "info_hash";
+pub const TOKEN_KEY:        &'static str = "token";
+pub const NODES_KEY:        &'static str = "nodes";
+pub const VALUES_KEY:       &'static str = "values";
+pub const PORT_KEY:         &'static str = "port";
+pub const IMPLIED_PORT_KEY: &'static str = "implied_port";
+
+/// Request variants, discriminated by the `"q"` method name.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum RequestType<'a> {
+    AnnouncePeer(AnnouncePeerRequest<'a>),
+    FindNode(FindNodeRequest<'a>),
+    GetPeers(GetPeersRequest<'a>),
+    Ping(PingRequest<'a>)
+}
+
+/// A response to one of our outgoing requests.
+///
+/// Unlike requests, a KRPC response carries no indication of which method it
+/// answers, so the raw `"r"` argument dictionary is handed back untouched;
+/// callers should correlate the transaction id against their own outstanding
+/// request and reparse with the matching `*Response::from_parts`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct GenericResponse<'a> {
+    trans_id: &'a [u8],
+    version:  Option<&'a [u8]>,
+    args:     &'a Dictionary<'a, Bencode<'a>>
+}
+
+impl<'a> GenericResponse<'a> {
+    pub fn transaction_id(&self) -> &'a [u8] {
+        self.trans_id
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn args(&self) -> &'a Dictionary<'a, Bencode<'a>> {
+        self.args
+    }
+}
+
+/// A single, fully decoded KRPC message.
+///
+/// This is the entry point consumers should use to decode any inbound
+/// datagram; `decode` inspects the top level `"y"` key to figure out which
+/// variant the message holds.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Message<'a> {
+    Request(RequestType<'a>),
+    Response(GenericResponse<'a>),
+    Error(error::ErrorMessage<'a>)
+}
+
+impl<'a> Message<'a> {
+    /// Decode a single KRPC message out of its bencoded dictionary form.
+    pub fn decode(bytes: &'a [u8]) -> DhtResult<Message<'a>> {
+        let bencode = try!(Bencode::decode(bytes).map_err(|_|
+            DhtError::new(DhtErrorKind::InvalidMessage, "Failed To Parse Message As Bencode")));
+        let message_root = try!(bencode.dict().ok_or(
+            DhtError::new(DhtErrorKind::InvalidMessage, "Message Is Not A Bencoded Dictionary")));
+
+        let validate = RequestValidate::new(&b""[..]);
+        let trans_id = try!(validate.lookup_and_convert_bytes(message_root, TRANSACTION_ID_KEY));
+        let version = validate.lookup_and_convert_bytes(message_root, CLIENT_TYPE_KEY).ok();
+        let message_type = try!(validate.lookup_and_convert_bytes(message_root, MESSAGE_TYPE_KEY));
+
+        if message_type == REQUEST_TYPE_KEY.as_bytes() {
+            let rqst_type = try!(validate.lookup_and_convert_bytes(message_root, REQUEST_TYPE_KEY));
+            let args_root = try!(validate.lookup_and_convert_dict(message_root, request::REQUEST_ARGS_KEY));
+
+            let rqst = if rqst_type == request::PING_TYPE_KEY.as_bytes() {
+                RequestType::Ping(try!(PingRequest::from_parts(args_root, trans_id, version)))
+            } else if rqst_type == request::FIND_NODE_TYPE_KEY.as_bytes() {
+                RequestType::FindNode(try!(FindNodeRequest::from_parts(args_root, trans_id, version)))
+            } else if rqst_type == request::GET_PEERS_TYPE_KEY.as_bytes() {
+                RequestType::GetPeers(try!(GetPeersRequest::from_parts(args_root, trans_id, version)))
+            } else if rqst_type == request::ANNOUNCE_PEER_TYPE_KEY.as_bytes() {
+                RequestType::AnnouncePeer(try!(AnnouncePeerRequest::from_parts(args_root, trans_id, version)))
+            } else {
+                return Err(DhtError::new(DhtErrorKind::InvalidRequest, "Unknown Request Type"));
+            };
+
+            Ok(Message::Request(rqst))
+        } else if message_type == RESPONSE_TYPE_KEY.as_bytes() {
+            let args_root = try!(validate.lookup_and_convert_dict(message_root, response::RESPONSE_ARGS_KEY));
+
+            Ok(Message::Response(GenericResponse{ trans_id: trans_id, version: version, args: args_root }))
+        } else if message_type == ERROR_TYPE_KEY.as_bytes() {
+            let error = try!(error::ErrorMessage::from_parts(message_root, trans_id, version));
+
+            Ok(Message::Error(error))
+        } else {
+            Err(DhtError::new(DhtErrorKind::InvalidMessage, "Unknown Message Type"))
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::{BTreeMap};
+
+use bip_bencode::{Bencode, BencodeConvert, Dictionary};
+use bip_util::bt::{NodeId};
+
+use message::{self};
+use message::request::{self, RequestValidate};
+use message::response::{self, ResponseValidate};
+use error::{DhtResult};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PingRequest<'a> {
+    trans_id: &'a [u8],
+    version:  Option<&'a [u8]>,
+    node_id:  NodeId
+}
+
+impl<'a> PingRequest<'a> {
+    pub fn new(trans_id: &'a [u8], node_id: NodeId) -> PingRequest<'a> {
+        PingRequest{ trans_id: trans_id, version: None, node_id: node_id }
+    }
+
+    pub fn with_version(trans_id: &'a [u8], version: &'a [u8], node_id: NodeId) -> PingRequest<'a> {
+        PingRequest{ trans_id: trans_id, version: Some(version), node_id: node_id }
+    }
+
+    pub fn from_parts(rqst_root: &Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8], version: Option<&'a [u8]>)
+        -> DhtResult<PingRequest<'a>> {
+        let validate = RequestValidate::new(trans_id);
+        let node_id_bytes = try!(validate.lookup_and_convert_bytes(rqst_root, message::NODE_ID_KEY));
+
+        let node_id = try!(validate.validate_node_id(node_id_bytes));
+
+        Ok(match version {
+            Some(version) => PingRequest::with_version(trans_id, version, node_id),
+            None          => PingRequest::new(trans_id, node_id)
+        })
+    }
+
+    pub fn transaction_id(&self) -> &'a [u8] {
+        self.trans_id
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message_root = BTreeMap::new();
+
+        message_root.insert(message::TRANSACTION_ID_KEY, ben_bytes!(self.trans_id));
+        message_root.insert(message::MESSAGE_TYPE_KEY, ben_bytes!(message::REQUEST_TYPE_KEY));
+        message_root.insert(message::REQUEST_TYPE_KEY, ben_bytes!(request::PING_TYPE_KEY));
+        if let Some(version) = self.version {
+            message_root.insert(message::CLIENT_TYPE_KEY, ben_bytes!(version));
+        }
+        message_root.insert(request::REQUEST_ARGS_KEY, ben_map!{
+            message::NODE_ID_KEY => ben_bytes!(self.node_id.as_bytes())
+        });
+
+        Bencode::Dict(message_root).encode()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PingResponse<'a> {
+    trans_id: &'a [u8],
+    version:  Option<&'a [u8]>,
+    node_id:  NodeId
+}
+
+impl<'a> PingResponse<'a> {
+    pub fn new(trans_id: &'a [u8], node_id: NodeId) -> PingResponse<'a> {
+        PingResponse{ trans_id: trans_id, version: None, node_id: node_id }
+    }
+
+    pub fn with_version(trans_id: &'a [u8], version: &'a [u8], node_id: NodeId) -> PingResponse<'a> {
+        PingResponse{ trans_id: trans_id, version: Some(version), node_id: node_id }
+    }
+
+    pub fn from_parts(rsp_root: &Dictionary<'a, Bencode<'a>>, trans_id: &'a [u8], version: Option<&'a [u8]>)
+        -> DhtResult<PingResponse<'a>> {
+        let validate = ResponseValidate::new(trans_id);
+        let node_id_bytes = try!(validate.lookup_and_convert_bytes(rsp_root, message::NODE_ID_KEY));
+
+        let node_id = try!(validate.validate_node_id(node_id_bytes));
+
+        Ok(match version {
+            Some(version) => PingResponse::with_version(trans_id, version, node_id),
+            None          => PingResponse::new(trans_id, node_id)
+        })
+    }
+
+    pub fn transaction_id(&self) -> &'a [u8] {
+        self.trans_id
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.version
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message_root = BTreeMap::new();
+
+        message_root.insert(message::TRANSACTION_ID_KEY, ben_bytes!(self.trans_id));
+        message_root.insert(message::MESSAGE_TYPE_KEY, ben_bytes!(message::RESPONSE_TYPE_KEY));
+        message_root.insert(message::REQUEST_TYPE_KEY, ben_bytes!(request::PING_TYPE_KEY));
+        if let Some(version) = self.version {
+            message_root.insert(message::CLIENT_TYPE_KEY, ben_bytes!(version));
+        }
+        message_root.insert(response::RESPONSE_ARGS_KEY, ben_map!{
+            message::NODE_ID_KEY => ben_bytes!(self.node_id.as_bytes())
+        });
+
+        Bencode::Dict(message_root).encode()
+    }
+}
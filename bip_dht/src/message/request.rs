@@ -0,0 +1,49 @@
+//! Helpers for validating and decoding the `"q"`/`"a"` portion of a request message.
+
+use bip_bencode::{BencodeConvert, BencodeConvertError};
+use bip_util::bt::{NodeId, InfoHash};
+
+use error::{DhtResult, DhtErrorKind, DhtError};
+
+/// Key under which a request's method specific arguments are nested.
+pub const REQUEST_ARGS_KEY: &'static str = "a";
+
+pub const PING_TYPE_KEY:          &'static str = "ping";
+pub const FIND_NODE_TYPE_KEY:     &'static str = "find_node";
+pub const GET_PEERS_TYPE_KEY:     &'static str = "get_peers";
+pub const ANNOUNCE_PEER_TYPE_KEY: &'static str = "announce_peer";
+
+/// Validates and decodes the arguments of an incoming request.
+///
+/// Since the wire format is symmetric, this is also reused to re-parse one of our own encoded
+/// requests (and, via `Message::decode`, to pull the transaction id/version out of any message).
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RequestValidate<'a> {
+    trans_id: &'a [u8]
+}
+
+impl<'a> RequestValidate<'a> {
+    pub fn new(trans_id: &'a [u8]) -> RequestValidate<'a> {
+        RequestValidate{ trans_id: trans_id }
+    }
+
+    /// Validate that `bytes` is a well formed `NodeId`.
+    pub fn validate_node_id(&self, bytes: &[u8]) -> DhtResult<NodeId> {
+        NodeId::from_bytes(bytes).map_err(|_| DhtError::new(DhtErrorKind::InvalidRequest,
+            "Node Id Was Not 20 Bytes Long"))
+    }
+
+    /// Validate that `bytes` is a well formed `InfoHash`.
+    pub fn validate_info_hash(&self, bytes: &[u8]) -> DhtResult<InfoHash> {
+        InfoHash::from_bytes(bytes).map_err(|_| DhtError::new(DhtErrorKind::InvalidRequest,
+            "Info Hash Was Not 20 Bytes Long"))
+    }
+}
+
+impl<'a> BencodeConvert for RequestValidate<'a> {
+    type Error = DhtError;
+
+    fn handle_error(&self, error: BencodeConvertError) -> DhtError {
+        DhtError::new(DhtErrorKind::InvalidRequest, error.description())
+    }
+}
@@ -0,0 +1,51 @@
+//! Helpers for validating and decoding the `"r"` portion of a response message.
+
+use bip_bencode::{Bencode, BencodeConvert, BencodeConvertError};
+use bip_util::bt::{NodeId};
+
+use message::compact_info::{self, CompactNodeInfo, CompactValueInfo};
+use error::{DhtResult, DhtErrorKind, DhtError};
+
+/// Key under which a response's method specific return values are nested.
+pub const RESPONSE_ARGS_KEY: &'static str = "r";
+
+/// Validates and decodes the return values of an incoming (or round-tripped outgoing) response.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ResponseValidate<'a> {
+    trans_id: &'a [u8]
+}
+
+impl<'a> ResponseValidate<'a> {
+    pub fn new(trans_id: &'a [u8]) -> ResponseValidate<'a> {
+        ResponseValidate{ trans_id: trans_id }
+    }
+
+    /// Validate that `bytes` is a well formed `NodeId`.
+    pub fn validate_node_id(&self, bytes: &[u8]) -> DhtResult<NodeId> {
+        NodeId::from_bytes(bytes).map_err(|_| DhtError::new(DhtErrorKind::InvalidResponse,
+            "Node Id Was Not 20 Bytes Long"))
+    }
+
+    /// Validate that `bytes` is a well formed, packed list of compact node info entries.
+    pub fn validate_nodes(&self, bytes: &'a [u8]) -> DhtResult<CompactNodeInfo<'a>> {
+        if bytes.len() % compact_info::NODE_INFO_LEN != 0 {
+            return Err(DhtError::new(DhtErrorKind::InvalidResponse,
+                "Nodes Was Not A Multiple Of A Single Compact Node Info Entry"));
+        }
+
+        Ok(CompactNodeInfo::new(bytes))
+    }
+
+    /// Validate that every element of `values` is a well formed compact peer entry.
+    pub fn validate_values(&self, values: &'a [Bencode<'a>]) -> DhtResult<CompactValueInfo<'a>> {
+        CompactValueInfo::new(values)
+    }
+}
+
+impl<'a> BencodeConvert for ResponseValidate<'a> {
+    type Error = DhtError;
+
+    fn handle_error(&self, error: BencodeConvertError) -> DhtError {
+        DhtError::new(DhtErrorKind::InvalidResponse, error.description())
+    }
+}
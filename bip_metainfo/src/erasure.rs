@@ -0,0 +1,316 @@
+//! Optional forward error correction for a group of pieces.
+//!
+//! Large swarms benefit from being able to reconstruct a missing piece from a subset of the
+//! pieces in its group rather than re-requesting it from a peer. This is a systematic
+//! Reed-Solomon code over GF(2^8): the first `data_shards` rows of the coding matrix are the
+//! identity (so a data shard is its own first-order encoding), and the remaining `parity_shards`
+//! rows are derived from a Vandermonde matrix so that any `data_shards` out of `data_shards +
+//! parity_shards` members are sufficient to recover the rest.
+
+use std::fmt::{self};
+use std::error::{self};
+
+use bip_util::sha::{self};
+
+/// Reed-Solomon's field polynomial, `x^8 + x^4 + x^3 + x^2 + 1`.
+const GF_POLY: u16 = 0x11d;
+
+/// Error returned when encoding or reconstructing a group of shards fails.
+#[derive(Debug, Clone)]
+pub struct ErasureError {
+    desc: &'static str
+}
+
+impl ErasureError {
+    fn new(desc: &'static str) -> ErasureError {
+        ErasureError{ desc: desc }
+    }
+}
+
+impl fmt::Display for ErasureError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "{}", self.desc)
+    }
+}
+
+impl error::Error for ErasureError {
+    fn description(&self) -> &str {
+        self.desc
+    }
+}
+
+pub type ErasureResult<T> = Result<T, ErasureError>;
+
+/// Reed-Solomon erasure coding over a single group of pieces.
+pub struct ErasureCoding {
+    data_shards:   usize,
+    parity_shards: usize,
+    // (data_shards + parity_shards) rows by data_shards columns, systematic (identity on top).
+    matrix:        Vec<Vec<u8>>,
+    // Expected SHA-1 hash of each of the data_shards pieces, used to verify a reconstruction.
+    piece_hashes:  Vec<[u8; sha::SHA_HASH_LEN]>
+}
+
+impl ErasureCoding {
+    /// Construct an `ErasureCoding` over a group of pieces, given their hashes in order and the
+    /// number of parity shards to generate alongside them.
+    pub fn new(piece_hashes: Vec<[u8; sha::SHA_HASH_LEN]>, parity_shards: usize) -> ErasureCoding {
+        let data_shards = piece_hashes.len();
+        let matrix = systematic_matrix(data_shards, parity_shards);
+
+        ErasureCoding{ data_shards: data_shards, parity_shards: parity_shards, matrix: matrix,
+            piece_hashes: piece_hashes }
+    }
+
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    /// Compute the parity shards for a complete, equal-length set of `data_shards` data shards.
+    pub fn encode(&self, shards: &[Vec<u8>]) -> ErasureResult<Vec<Vec<u8>>> {
+        if shards.len() != self.data_shards {
+            return Err(ErasureError::new("Expected Exactly data_shards Shards To Encode"));
+        }
+
+        let shard_len = try!(shards.get(0).ok_or(ErasureError::new("Cannot Encode An Empty Shard Group")))
+            .len();
+        if shards.iter().any(|shard| shard.len() != shard_len) {
+            return Err(ErasureError::new("All Shards Being Encoded Must Be The Same Length"));
+        }
+
+        let mut parity = vec![vec![0u8; shard_len]; self.parity_shards];
+        for parity_index in 0..self.parity_shards {
+            let row = &self.matrix[self.data_shards + parity_index];
+
+            for byte_index in 0..shard_len {
+                let mut sum = 0u8;
+                for data_index in 0..self.data_shards {
+                    sum ^= gf_mul(row[data_index], shards[data_index][byte_index]);
+                }
+
+                parity[parity_index][byte_index] = sum;
+            }
+        }
+
+        Ok(parity)
+    }
+
+    /// Reconstruct any missing data shards in place.
+    ///
+    /// `shards` must have exactly `data_shards + parity_shards` slots, with data shards first
+    /// followed by parity shards, in the same order they were generated. At least `data_shards`
+    /// of them must be `Some`. Every reconstructed data shard is checked against its stored
+    /// SHA-1 hash before being accepted; a mismatch is treated as a failed reconstruction.
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> ErasureResult<()> {
+        if shards.len() != self.data_shards + self.parity_shards {
+            return Err(ErasureError::new("Expected data_shards + parity_shards Shard Slots"));
+        }
+
+        if shards[0..self.data_shards].iter().all(|shard| shard.is_some()) {
+            return Ok(());
+        }
+
+        let present: Vec<usize> = (0..shards.len()).filter(|&index| shards[index].is_some()).collect();
+        if present.len() < self.data_shards {
+            return Err(ErasureError::new("Not Enough Shards Present To Reconstruct"));
+        }
+
+        let used: Vec<usize> = present.into_iter().take(self.data_shards).collect();
+        let shard_len = shards[used[0]].as_ref().unwrap().len();
+        if used.iter().any(|&index| shards[index].as_ref().unwrap().len() != shard_len) {
+            return Err(ErasureError::new("All Present Shards Must Be The Same Length"));
+        }
+
+        let sub_matrix: Vec<Vec<u8>> = used.iter().map(|&index| self.matrix[index].clone()).collect();
+        let inverse = try!(invert_matrix(&sub_matrix));
+
+        let missing: Vec<usize> = (0..self.data_shards).filter(|&index| shards[index].is_none()).collect();
+        let mut recovered = vec![vec![0u8; shard_len]; missing.len()];
+
+        for byte_index in 0..shard_len {
+            let known: Vec<u8> = used.iter().map(|&index| shards[index].as_ref().unwrap()[byte_index]).collect();
+
+            for (recovered_index, &missing_index) in missing.iter().enumerate() {
+                let mut sum = 0u8;
+                for (k, &known_byte) in known.iter().enumerate() {
+                    sum ^= gf_mul(inverse[missing_index][k], known_byte);
+                }
+
+                recovered[recovered_index][byte_index] = sum;
+            }
+        }
+
+        for (recovered_index, &missing_index) in missing.iter().enumerate() {
+            let hash = sha::sha1_hash(&recovered[recovered_index]);
+            if hash != self.piece_hashes[missing_index] {
+                return Err(ErasureError::new("Reconstructed Piece Did Not Match Its Stored Hash"));
+            }
+
+            shards[missing_index] = Some(recovered[recovered_index].clone());
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a systematic coding matrix: the top `data_shards` rows are the identity matrix, and the
+/// bottom `parity_shards` rows are derived from a Vandermonde matrix so that every square
+/// sub-matrix formed from any `data_shards` of the rows is invertible.
+fn systematic_matrix(data_shards: usize, parity_shards: usize) -> Vec<Vec<u8>> {
+    let total_shards = data_shards + parity_shards;
+
+    // vandermonde[i][j] = (i + 1) ^ j, for i in 0..total_shards, j in 0..data_shards.
+    let vandermonde: Vec<Vec<u8>> = (0..total_shards).map(|i| {
+        (0..data_shards).map(|j| gf_pow((i + 1) as u8, j as u32)).collect()
+    }).collect();
+
+    let top: Vec<Vec<u8>> = vandermonde[0..data_shards].to_vec();
+    let top_inverse = invert_matrix(&top).expect("bip_metainfo: Vandermonde Top Block Was Not Invertible");
+
+    vandermonde.iter().map(|row| vector_matrix_mul(row, &top_inverse)).collect()
+}
+
+/// Multiply the row vector `vector` by `matrix`.
+fn vector_matrix_mul(vector: &[u8], matrix: &[Vec<u8>]) -> Vec<u8> {
+    let columns = matrix.get(0).map(|row| row.len()).unwrap_or(0);
+
+    (0..columns).map(|col| {
+        vector.iter().zip(matrix.iter()).fold(0u8, |acc, (&a, row)| acc ^ gf_mul(a, row[col]))
+    }).collect()
+}
+
+/// Invert a square matrix over GF(2^8) via Gauss-Jordan elimination.
+fn invert_matrix(matrix: &[Vec<u8>]) -> ErasureResult<Vec<Vec<u8>>> {
+    let size = matrix.len();
+
+    let mut work: Vec<Vec<u8>> = matrix.iter().cloned().collect();
+    let mut inverse: Vec<Vec<u8>> = (0..size).map(|i| {
+        (0..size).map(|j| if i == j { 1u8 } else { 0u8 }).collect()
+    }).collect();
+
+    for col in 0..size {
+        let pivot_row = try!((col..size).find(|&row| work[row][col] != 0)
+            .ok_or(ErasureError::new("Matrix Was Not Invertible")));
+
+        work.swap(col, pivot_row);
+        inverse.swap(col, pivot_row);
+
+        let pivot_inverse = gf_inverse(work[col][col]);
+        for value in work[col].iter_mut() {
+            *value = gf_mul(*value, pivot_inverse);
+        }
+        for value in inverse[col].iter_mut() {
+            *value = gf_mul(*value, pivot_inverse);
+        }
+
+        for row in 0..size {
+            if row == col {
+                continue;
+            }
+
+            let factor = work[row][col];
+            if factor == 0 {
+                continue;
+            }
+
+            for c in 0..size {
+                work[row][c] ^= gf_mul(factor, work[col][c]);
+                inverse[row][c] ^= gf_mul(factor, inverse[col][c]);
+            }
+        }
+    }
+
+    Ok(inverse)
+}
+
+fn gf_mul(a: u8, b: u8) -> u8 {
+    let (mut a, mut b) = (a as u16, b as u16);
+    let mut product = 0u16;
+
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+
+        a <<= 1;
+        if a & 0x100 != 0 {
+            a ^= GF_POLY;
+        }
+
+        b >>= 1;
+    }
+
+    product as u8
+}
+
+fn gf_pow(mut base: u8, mut exponent: u32) -> u8 {
+    let mut result = 1u8;
+
+    while exponent > 0 {
+        if exponent & 1 != 0 {
+            result = gf_mul(result, base);
+        }
+
+        base = gf_mul(base, base);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+fn gf_inverse(a: u8) -> u8 {
+    // GF(2^8)* is cyclic of order 255, so a^254 == a^-1 for every non zero a.
+    gf_pow(a, 254)
+}
+
+#[cfg(test)]
+mod tests {
+    use bip_util::sha::{self};
+
+    use super::{ErasureCoding};
+
+    #[test]
+    fn positive_reconstruct_recovers_single_missing_data_shard() {
+        let data = vec![
+            b"zero zero zero zero!".to_vec(),
+            b"one one one one one!".to_vec(),
+            b"two two two two two!".to_vec()
+        ];
+        let hashes: Vec<[u8; sha::SHA_HASH_LEN]> = data.iter().map(|shard| sha::sha1_hash(shard)).collect();
+
+        let coding = ErasureCoding::new(hashes, 2);
+        let parity = coding.encode(&data).unwrap();
+
+        let mut shards: Vec<Option<Vec<u8>>> = data.iter().cloned().map(Some)
+            .chain(parity.iter().cloned().map(Some)).collect();
+        shards[1] = None;
+
+        coding.reconstruct(&mut shards).unwrap();
+
+        assert_eq!(shards[1], Some(data[1].clone()));
+    }
+
+    #[test]
+    fn negative_reconstruct_rejects_mismatched_shard_lengths() {
+        let data = vec![
+            b"zero zero zero zero!".to_vec(),
+            b"one one one one one!".to_vec(),
+            b"two two two two two!".to_vec()
+        ];
+        let hashes: Vec<[u8; sha::SHA_HASH_LEN]> = data.iter().map(|shard| sha::sha1_hash(shard)).collect();
+
+        let coding = ErasureCoding::new(hashes, 2);
+        let parity = coding.encode(&data).unwrap();
+
+        let mut shards: Vec<Option<Vec<u8>>> = data.iter().cloned().map(Some)
+            .chain(parity.iter().cloned().map(Some)).collect();
+        shards[0] = None;
+        shards[1] = Some(b"too short".to_vec());
+
+        assert!(coding.reconstruct(&mut shards).is_err());
+    }
+}
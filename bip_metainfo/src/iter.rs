@@ -32,6 +32,9 @@ impl<'a> Iterator for Files<'a> {
 //----------------------------------------------------------------------------//
 
 /// Iterator over each piece hash within the MetainfoFile.
+///
+/// This only applies to metainfo files that store a flat, per-piece hash list; BEP-30 Merkle
+/// tree torrents store a single root hash instead and are represented by `merkle::MerkleTree`.
 pub struct Pieces<'a> {
     index:  usize,
     pieces: &'a [[u8; sha::SHA_HASH_LEN]]
@@ -0,0 +1,178 @@
+//! Support for BEP-30 Merkle tree torrents.
+//!
+//! A Merkle torrent's info dictionary stores a single `root hash` (and an implied tree height)
+//! instead of the flat, per-piece hash list that `Pieces` iterates over. A piece can still be
+//! verified on the fly as it arrives, using the root hash plus the sibling proof that came with
+//! the piece over the wire.
+
+use std::fmt::{self};
+use std::error::{self};
+
+use bip_util::sha::{self};
+
+/// Error returned when a sibling proof passed to `MerkleTree::verify_piece` is malformed.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct InvalidProofError {
+    expected: usize,
+    actual:   usize
+}
+
+impl InvalidProofError {
+    fn new(expected: usize, actual: usize) -> InvalidProofError {
+        InvalidProofError{ expected: expected, actual: actual }
+    }
+}
+
+impl fmt::Display for InvalidProofError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "expected a proof of length {}, found {}", self.expected, self.actual)
+    }
+}
+
+impl error::Error for InvalidProofError {
+    fn description(&self) -> &str {
+        "sibling proof length did not match the tree height"
+    }
+}
+
+/// The root hash and height of a BEP-30 Merkle tree torrent.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MerkleTree {
+    root_hash: [u8; sha::SHA_HASH_LEN],
+    height:    u32
+}
+
+impl MerkleTree {
+    /// Construct a `MerkleTree` from its root hash and the total number of pieces in the torrent.
+    ///
+    /// The height is derived by padding `num_pieces` up to the next power of two.
+    pub fn new(root_hash: [u8; sha::SHA_HASH_LEN], num_pieces: usize) -> MerkleTree {
+        MerkleTree{ root_hash: root_hash, height: height_for_pieces(num_pieces) }
+    }
+
+    /// Construct a `MerkleTree` from a root hash read straight off the wire/info dict.
+    ///
+    /// Returns `None` if `bytes` is not exactly `sha::SHA_HASH_LEN` bytes long.
+    pub fn from_bytes(bytes: &[u8], num_pieces: usize) -> Option<MerkleTree> {
+        if bytes.len() != sha::SHA_HASH_LEN {
+            return None;
+        }
+
+        let mut root_hash = [0u8; sha::SHA_HASH_LEN];
+        for (dst, src) in root_hash.iter_mut().zip(bytes.iter()) {
+            *dst = *src;
+        }
+
+        Some(MerkleTree::new(root_hash, num_pieces))
+    }
+
+    pub fn root_hash(&self) -> &[u8; sha::SHA_HASH_LEN] {
+        &self.root_hash
+    }
+
+    /// Number of levels that must be climbed from a leaf to reach the root hash.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Verify that `piece_data` is the piece at `index`, given its sibling proof.
+    ///
+    /// `proof` must contain exactly `height` sibling hashes, ordered from the leaf's sibling up
+    /// to the root's child; bit `k` of `index` decides whether our node is the left or right
+    /// child at level `k` (a set bit means we are the right child). Returns `Err` if `proof` is
+    /// not exactly `height` hashes long; otherwise `Ok(true)`/`Ok(false)` reports whether the
+    /// computed root matched the stored root hash.
+    pub fn verify_piece(&self, index: usize, piece_data: &[u8], proof: &[[u8; sha::SHA_HASH_LEN]])
+        -> Result<bool, InvalidProofError> {
+        if proof.len() != self.height as usize {
+            return Err(InvalidProofError::new(self.height as usize, proof.len()));
+        }
+
+        let mut hash = sha::sha1_hash(piece_data);
+
+        for (level, sibling) in proof.iter().enumerate() {
+            let our_node_is_right_child = (index >> level) & 1 == 1;
+
+            hash = if our_node_is_right_child {
+                sha::sha1_hash(&concat_hashes(sibling, &hash))
+            } else {
+                sha::sha1_hash(&concat_hashes(&hash, sibling))
+            };
+        }
+
+        Ok(hash == self.root_hash)
+    }
+}
+
+fn concat_hashes(left: &[u8; sha::SHA_HASH_LEN], right: &[u8; sha::SHA_HASH_LEN]) -> Vec<u8> {
+    let mut combined = Vec::with_capacity(sha::SHA_HASH_LEN * 2);
+    combined.extend_from_slice(left);
+    combined.extend_from_slice(right);
+
+    combined
+}
+
+/// Height of the smallest perfect binary tree with at least `num_pieces` leaves.
+fn height_for_pieces(num_pieces: usize) -> u32 {
+    let mut height = 0;
+    let mut leaves = 1usize;
+
+    while leaves < num_pieces {
+        leaves <<= 1;
+        height += 1;
+    }
+
+    height
+}
+
+#[cfg(test)]
+mod tests {
+    use bip_util::sha::{self};
+
+    use super::{MerkleTree};
+
+    fn combine(left: &[u8; sha::SHA_HASH_LEN], right: &[u8; sha::SHA_HASH_LEN]) -> [u8; sha::SHA_HASH_LEN] {
+        let mut combined = Vec::with_capacity(sha::SHA_HASH_LEN * 2);
+        combined.extend_from_slice(left);
+        combined.extend_from_slice(right);
+
+        sha::sha1_hash(&combined)
+    }
+
+    #[test]
+    fn positive_verify_piece_matches_two_leaf_tree() {
+        let piece_zero = b"piece zero bytes";
+        let piece_one = b"piece one bytes!";
+
+        let leaf_zero = sha::sha1_hash(piece_zero);
+        let leaf_one = sha::sha1_hash(piece_one);
+        let root = combine(&leaf_zero, &leaf_one);
+
+        let tree = MerkleTree::new(root, 2);
+
+        assert_eq!(tree.height(), 1);
+        assert_eq!(tree.verify_piece(0, piece_zero, &[leaf_one]), Ok(true));
+        assert_eq!(tree.verify_piece(1, piece_one, &[leaf_zero]), Ok(true));
+    }
+
+    #[test]
+    fn negative_verify_piece_rejects_wrong_data() {
+        let piece_zero = b"piece zero bytes";
+        let piece_one = b"piece one bytes!";
+
+        let leaf_zero = sha::sha1_hash(piece_zero);
+        let leaf_one = sha::sha1_hash(piece_one);
+        let root = combine(&leaf_zero, &leaf_one);
+
+        let tree = MerkleTree::new(root, 2);
+
+        assert_eq!(tree.verify_piece(0, piece_one, &[leaf_one]), Ok(false));
+    }
+
+    #[test]
+    fn negative_verify_piece_rejects_wrong_length_proof() {
+        let tree = MerkleTree::new([0u8; sha::SHA_HASH_LEN], 4);
+
+        assert!(tree.verify_piece(0, b"piece", &[]).is_err());
+    }
+}
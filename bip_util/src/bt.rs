@@ -0,0 +1,100 @@
+//! BitTorrent protocol primitives shared across the bip-rs crates.
+
+use std::fmt::{self};
+
+use sha::{self};
+
+/// Length, in bytes, of a `NodeId`.
+pub const NODE_ID_LEN: usize = sha::SHA_HASH_LEN;
+
+/// Length, in bytes, of an `InfoHash`.
+pub const INFO_HASH_LEN: usize = sha::SHA_HASH_LEN;
+
+/// Error returned when a byte slice is not the expected fixed length.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct InvalidIdError {
+    expected: usize,
+    actual:   usize
+}
+
+impl InvalidIdError {
+    fn new(expected: usize, actual: usize) -> InvalidIdError {
+        InvalidIdError{ expected: expected, actual: actual }
+    }
+}
+
+impl fmt::Display for InvalidIdError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "expected {} bytes, found {}", self.expected, self.actual)
+    }
+}
+
+/// A 20 byte identifier for a node within the dht.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NodeId([u8; NODE_ID_LEN]);
+
+impl NodeId {
+    /// Validate and construct a `NodeId` from a byte slice of the correct length.
+    pub fn from_bytes(bytes: &[u8]) -> Result<NodeId, InvalidIdError> {
+        if bytes.len() != NODE_ID_LEN {
+            return Err(InvalidIdError::new(NODE_ID_LEN, bytes.len()));
+        }
+
+        let mut id = [0u8; NODE_ID_LEN];
+        for (dst, src) in id.iter_mut().zip(bytes.iter()) {
+            *dst = *src;
+        }
+
+        Ok(NodeId(id))
+    }
+
+    /// Construct a `NodeId` from an already correctly sized array.
+    pub fn from_array(bytes: [u8; NODE_ID_LEN]) -> NodeId {
+        NodeId(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for NodeId {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A 20 byte SHA-1 hash identifying a torrent within the dht.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct InfoHash([u8; INFO_HASH_LEN]);
+
+impl InfoHash {
+    /// Validate and construct an `InfoHash` from a byte slice of the correct length.
+    pub fn from_bytes(bytes: &[u8]) -> Result<InfoHash, InvalidIdError> {
+        if bytes.len() != INFO_HASH_LEN {
+            return Err(InvalidIdError::new(INFO_HASH_LEN, bytes.len()));
+        }
+
+        let mut hash = [0u8; INFO_HASH_LEN];
+        for (dst, src) in hash.iter_mut().zip(bytes.iter()) {
+            *dst = *src;
+        }
+
+        Ok(InfoHash(hash))
+    }
+
+    /// Construct an `InfoHash` from an already correctly sized array.
+    pub fn from_array(bytes: [u8; INFO_HASH_LEN]) -> InfoHash {
+        InfoHash(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for InfoHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}